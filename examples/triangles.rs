@@ -17,11 +17,11 @@ fn main() -> Result<(), Error> {
 
     let vert = shader::Vertex::from_file("examples/shaders/triangle.vert")?;
     let frag = shader::Fragment::from_file("examples/shaders/triangle.frag")?;
-    let prog = shader::Program::new(vert, frag)?;
+    let _prog = shader::Program::new(vert, frag)?;
 
     el.run(move |event, _, control_flow| {
         match event {
-            Event::LoopDestroyed => return,
+            Event::LoopDestroyed => (),
             Event::WindowEvent { event, .. } => match event {
                 WindowEvent::Resized(physical_size) => windowed_context.resize(physical_size),
                 WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
@@ -0,0 +1,299 @@
+use gl::types::*;
+
+use std::cell::OnceCell;
+use std::collections::HashSet;
+use std::ffi::CStr;
+use std::fmt;
+
+use crate::error::Error;
+
+thread_local! {
+    // GL contexts are current on a single thread so the extension set is cached per thread.
+    static EXTENSIONS: OnceCell<HashSet<String>> = const { OnceCell::new() };
+}
+
+/// Checks whether the current context supports the named extension (e.g.
+/// `"GL_ARB_bindless_texture"`). The extension list is queried once and then cached.
+pub fn has_extension(name: &str) -> bool {
+    EXTENSIONS.with(|exts| exts.get_or_init(query_extensions).contains(name))
+}
+
+/// The OpenGL version of the current context as `(major, minor)`, parsed from `GL_VERSION` so that
+/// it works on contexts older than 3.0. Returns `(0, 0)` if the version string is unrecognised.
+pub(crate) fn context_version() -> (u8, u8) {
+    let ptr = unsafe { gl::GetString(gl::VERSION) };
+    if ptr.is_null() {
+        return (0, 0);
+    }
+
+    let version = unsafe { CStr::from_ptr(ptr as *const _) };
+    parse_version(&version.to_string_lossy()).unwrap_or((0, 0))
+}
+
+// The version string starts with "<major>.<minor>" (optionally preceded by e.g. "OpenGL ES ")
+// followed by vendor-specific information.
+fn parse_version(version: &str) -> Option<(u8, u8)> {
+    let number = version
+        .split_whitespace()
+        .find(|word| word.starts_with(|c: char| c.is_ascii_digit()))?;
+    let mut parts = number.split('.');
+
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts
+        .next()?
+        .trim_end_matches(|c: char| !c.is_ascii_digit())
+        .parse()
+        .ok()?;
+
+    Some((major, minor))
+}
+
+/// Whether program pipelines are available, i.e. the context is OpenGL 4.1 or later or supports
+/// `GL_ARB_separate_shader_objects`.
+pub(crate) fn supports_program_pipelines() -> bool {
+    context_version() >= (4, 1) || has_extension("GL_ARB_separate_shader_objects")
+}
+
+fn query_extensions() -> HashSet<String> {
+    let mut count: GLint = 0;
+    unsafe {
+        gl::GetIntegerv(gl::NUM_EXTENSIONS, &mut count);
+    }
+
+    (0..count.max(0) as GLuint)
+        .filter_map(|i| {
+            let ptr = unsafe { gl::GetStringi(gl::EXTENSIONS, i) };
+            if ptr.is_null() {
+                None
+            } else {
+                let name = unsafe { CStr::from_ptr(ptr as *const _) };
+                Some(name.to_string_lossy().into_owned())
+            }
+        })
+        .collect()
+}
+
+/// A snapshot of commonly relevant GL state, as returned by [`dump_state`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateDump {
+    pub vertex_array: GLuint,
+    pub program: GLuint,
+    /// `None` if program pipelines are not supported by the context.
+    pub program_pipeline: Option<GLuint>,
+    pub draw_framebuffer: GLuint,
+    /// Index of the active texture unit (i.e. 0 for `GL_TEXTURE0`).
+    pub active_texture_unit: GLuint,
+    pub blend: bool,
+    pub depth_test: bool,
+    pub cull_face: bool,
+    pub scissor_test: bool,
+}
+
+impl fmt::Display for StateDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "vertex array: {}", self.vertex_array)?;
+        writeln!(f, "program: {}", self.program)?;
+        match self.program_pipeline {
+            Some(pipeline) => writeln!(f, "program pipeline: {}", pipeline)?,
+            None => writeln!(f, "program pipeline: unsupported")?,
+        }
+        writeln!(f, "draw framebuffer: {}", self.draw_framebuffer)?;
+        writeln!(f, "active texture unit: {}", self.active_texture_unit)?;
+        writeln!(f, "blend: {}", self.blend)?;
+        writeln!(f, "depth test: {}", self.depth_test)?;
+        writeln!(f, "cull face: {}", self.cull_face)?;
+        write!(f, "scissor test: {}", self.scissor_test)
+    }
+}
+
+/// Queries the current bindings and enabled capabilities, which is useful for figuring out why
+/// nothing is being rendered. The result implements `Display` for printing or logging.
+pub fn dump_state() -> StateDump {
+    StateDump {
+        vertex_array: get_integer(gl::VERTEX_ARRAY_BINDING) as GLuint,
+        program: get_integer(gl::CURRENT_PROGRAM) as GLuint,
+        program_pipeline: supports_program_pipelines()
+            .then(|| get_integer(gl::PROGRAM_PIPELINE_BINDING) as GLuint),
+        draw_framebuffer: get_integer(gl::DRAW_FRAMEBUFFER_BINDING) as GLuint,
+        active_texture_unit: (get_integer(gl::ACTIVE_TEXTURE) as GLuint)
+            .saturating_sub(gl::TEXTURE0),
+        blend: is_enabled(gl::BLEND),
+        depth_test: is_enabled(gl::DEPTH_TEST),
+        cull_face: is_enabled(gl::CULL_FACE),
+        scissor_test: is_enabled(gl::SCISSOR_TEST),
+    }
+}
+
+fn get_integer(name: GLenum) -> GLint {
+    let mut value = 0;
+    unsafe {
+        gl::GetIntegerv(name, &mut value);
+    }
+    value
+}
+
+fn is_enabled(cap: GLenum) -> bool {
+    unsafe { gl::IsEnabled(cap) == gl::TRUE }
+}
+
+/// The source of a debug message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugSource {
+    Api,
+    WindowSystem,
+    ShaderCompiler,
+    ThirdParty,
+    Application,
+    Other,
+}
+
+impl From<DebugSource> for GLenum {
+    fn from(source: DebugSource) -> GLenum {
+        match source {
+            DebugSource::Api => gl::DEBUG_SOURCE_API,
+            DebugSource::WindowSystem => gl::DEBUG_SOURCE_WINDOW_SYSTEM,
+            DebugSource::ShaderCompiler => gl::DEBUG_SOURCE_SHADER_COMPILER,
+            DebugSource::ThirdParty => gl::DEBUG_SOURCE_THIRD_PARTY,
+            DebugSource::Application => gl::DEBUG_SOURCE_APPLICATION,
+            DebugSource::Other => gl::DEBUG_SOURCE_OTHER,
+        }
+    }
+}
+
+impl TryFrom<GLenum> for DebugSource {
+    type Error = Error;
+
+    fn try_from(value: GLenum) -> Result<Self, Error> {
+        match value {
+            gl::DEBUG_SOURCE_API => Ok(DebugSource::Api),
+            gl::DEBUG_SOURCE_WINDOW_SYSTEM => Ok(DebugSource::WindowSystem),
+            gl::DEBUG_SOURCE_SHADER_COMPILER => Ok(DebugSource::ShaderCompiler),
+            gl::DEBUG_SOURCE_THIRD_PARTY => Ok(DebugSource::ThirdParty),
+            gl::DEBUG_SOURCE_APPLICATION => Ok(DebugSource::Application),
+            gl::DEBUG_SOURCE_OTHER => Ok(DebugSource::Other),
+            other => Err(Error::UnknownEnum(other)),
+        }
+    }
+}
+
+/// The type of a debug message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugType {
+    Error,
+    DeprecatedBehavior,
+    UndefinedBehavior,
+    Portability,
+    Performance,
+    Marker,
+    PushGroup,
+    PopGroup,
+    Other,
+}
+
+impl From<DebugType> for GLenum {
+    fn from(ty: DebugType) -> GLenum {
+        match ty {
+            DebugType::Error => gl::DEBUG_TYPE_ERROR,
+            DebugType::DeprecatedBehavior => gl::DEBUG_TYPE_DEPRECATED_BEHAVIOR,
+            DebugType::UndefinedBehavior => gl::DEBUG_TYPE_UNDEFINED_BEHAVIOR,
+            DebugType::Portability => gl::DEBUG_TYPE_PORTABILITY,
+            DebugType::Performance => gl::DEBUG_TYPE_PERFORMANCE,
+            DebugType::Marker => gl::DEBUG_TYPE_MARKER,
+            DebugType::PushGroup => gl::DEBUG_TYPE_PUSH_GROUP,
+            DebugType::PopGroup => gl::DEBUG_TYPE_POP_GROUP,
+            DebugType::Other => gl::DEBUG_TYPE_OTHER,
+        }
+    }
+}
+
+impl TryFrom<GLenum> for DebugType {
+    type Error = Error;
+
+    fn try_from(value: GLenum) -> Result<Self, Error> {
+        match value {
+            gl::DEBUG_TYPE_ERROR => Ok(DebugType::Error),
+            gl::DEBUG_TYPE_DEPRECATED_BEHAVIOR => Ok(DebugType::DeprecatedBehavior),
+            gl::DEBUG_TYPE_UNDEFINED_BEHAVIOR => Ok(DebugType::UndefinedBehavior),
+            gl::DEBUG_TYPE_PORTABILITY => Ok(DebugType::Portability),
+            gl::DEBUG_TYPE_PERFORMANCE => Ok(DebugType::Performance),
+            gl::DEBUG_TYPE_MARKER => Ok(DebugType::Marker),
+            gl::DEBUG_TYPE_PUSH_GROUP => Ok(DebugType::PushGroup),
+            gl::DEBUG_TYPE_POP_GROUP => Ok(DebugType::PopGroup),
+            gl::DEBUG_TYPE_OTHER => Ok(DebugType::Other),
+            other => Err(Error::UnknownEnum(other)),
+        }
+    }
+}
+
+/// The severity of a debug message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugSeverity {
+    High,
+    Medium,
+    Low,
+    Notification,
+}
+
+impl From<DebugSeverity> for GLenum {
+    fn from(severity: DebugSeverity) -> GLenum {
+        match severity {
+            DebugSeverity::High => gl::DEBUG_SEVERITY_HIGH,
+            DebugSeverity::Medium => gl::DEBUG_SEVERITY_MEDIUM,
+            DebugSeverity::Low => gl::DEBUG_SEVERITY_LOW,
+            DebugSeverity::Notification => gl::DEBUG_SEVERITY_NOTIFICATION,
+        }
+    }
+}
+
+impl TryFrom<GLenum> for DebugSeverity {
+    type Error = Error;
+
+    fn try_from(value: GLenum) -> Result<Self, Error> {
+        match value {
+            gl::DEBUG_SEVERITY_HIGH => Ok(DebugSeverity::High),
+            gl::DEBUG_SEVERITY_MEDIUM => Ok(DebugSeverity::Medium),
+            gl::DEBUG_SEVERITY_LOW => Ok(DebugSeverity::Low),
+            gl::DEBUG_SEVERITY_NOTIFICATION => Ok(DebugSeverity::Notification),
+            other => Err(Error::UnknownEnum(other)),
+        }
+    }
+}
+
+/// Enables or disables debug output messages matching the given source, type and severity, where
+/// `None` matches any value. For example, passing `Some(DebugSource::ShaderCompiler)`,
+/// `Some(DebugType::Performance)`, `None` and `false` mutes all shader compiler performance
+/// warnings.
+pub fn set_message_control(
+    source: Option<DebugSource>,
+    msg_type: Option<DebugType>,
+    severity: Option<DebugSeverity>,
+    enabled: bool,
+) {
+    unsafe {
+        gl::DebugMessageControl(
+            source.map_or(gl::DONT_CARE, GLenum::from),
+            msg_type.map_or(gl::DONT_CARE, GLenum::from),
+            severity.map_or(gl::DONT_CARE, GLenum::from),
+            0,
+            std::ptr::null(),
+            enabled as GLboolean,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_version_strings() {
+        assert_eq!(parse_version("4.6.0 NVIDIA 535.54.03"), Some((4, 6)));
+        assert_eq!(parse_version("2.1 Metal - 83.1"), Some((2, 1)));
+        assert_eq!(
+            parse_version("3.3 (Core Profile) Mesa 23.0.4"),
+            Some((3, 3))
+        );
+        assert_eq!(parse_version("OpenGL ES 3.2 v1.r32p1"), Some((3, 2)));
+        assert_eq!(parse_version("unknown"), None);
+    }
+}
@@ -1,3 +1,5 @@
+use gl::types::GLenum;
+
 use std::ffi::NulError;
 
 #[derive(thiserror::Error, Debug)]
@@ -6,14 +8,33 @@ pub enum Error {
     CString(#[from] NulError),
     #[error("Shader error: {0}")]
     Shader(#[from] ShaderError),
+    #[error(
+        "OpenGL {}.{} or later is required but the current context is {}.{}",
+        required.0, required.1, found.0, found.1
+    )]
+    UnsupportedVersion { required: (u8, u8), found: (u8, u8) },
+    #[error("Unrecognised GL enum value: {0:#x}")]
+    UnknownEnum(GLenum),
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum ShaderError {
     #[error("failed to load shader - {0}")]
     Loading(#[from] std::io::Error),
+    #[error("shader source is empty")]
+    EmptySource,
     #[error("failed to compile shader - {0}")]
     Compilation(String),
     #[error("failed to link shader - {0}")]
     Linking(String),
+    #[error("no active subroutine or subroutine uniform named '{0}'")]
+    SubroutineNotFound(String),
+    #[error("program must be in use to set its subroutines")]
+    ProgramNotInUse,
+    #[error("expected {expected} subroutine indices (one per active subroutine uniform location) but got {found}")]
+    SubroutineCountMismatch { expected: usize, found: usize },
+    #[error("unknown shader section '{0}' in combined shader source")]
+    UnknownSection(String),
+    #[error("missing {0} section in combined shader source")]
+    MissingSection(&'static str),
 }
@@ -1,7 +1,48 @@
+pub mod debug;
 pub mod error;
+pub mod limits;
+pub mod rendering;
 pub mod shader;
+pub mod shapes;
+#[cfg(feature = "dev")]
+pub mod test_support;
 
 pub use error::Error;
 
+/// The minimum OpenGL version required by the core functionality of this crate. Some functions
+/// (e.g. program pipelines and program interface queries) need a later version.
+pub fn required_version() -> (u8, u8) {
+    (3, 3)
+}
+
+/// Checks that the current context's OpenGL version is at least [`required_version`].
+pub fn check_context() -> Result<(), Error> {
+    let found = debug::context_version();
+    let required = required_version();
+
+    if found < required {
+        return Err(Error::UnsupportedVersion { required, found });
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
-mod tests {}
+mod tests {
+    #[cfg(feature = "dev")]
+    #[test]
+    #[ignore = "requires an OpenGL context; run with `cargo test --features dev -- --ignored`"]
+    fn link_program_in_test_context() {
+        use crate::shader::Program;
+        use crate::test_support::with_test_context;
+
+        with_test_context(|| {
+            crate::check_context().unwrap();
+
+            let vert = "#version 330 core\nvoid main() { gl_Position = vec4(0.0); }";
+            let frag = "#version 330 core\nout vec4 c;\nvoid main() { c = vec4(1.0); }";
+            let prog = Program::from_sources(vert, frag).unwrap();
+            assert_ne!(prog.get_id(), 0);
+        });
+    }
+}
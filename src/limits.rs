@@ -0,0 +1,32 @@
+use gl::types::*;
+
+use std::cell::OnceCell;
+
+use crate::debug::has_extension;
+
+// Not part of the core bindings until OpenGL 4.6.
+const MAX_TEXTURE_MAX_ANISOTROPY: GLenum = 0x84FF;
+
+thread_local! {
+    static MAX_ANISOTROPY: OnceCell<GLfloat> = const { OnceCell::new() };
+}
+
+/// The maximum supported anisotropic filtering level, or 1.0 (i.e. no anisotropic filtering) if
+/// the anisotropic filtering extension is unavailable. The value is queried once and then cached.
+pub fn max_anisotropy() -> GLfloat {
+    MAX_ANISOTROPY.with(|max| *max.get_or_init(query_max_anisotropy))
+}
+
+fn query_max_anisotropy() -> GLfloat {
+    if !has_extension("GL_ARB_texture_filter_anisotropic")
+        && !has_extension("GL_EXT_texture_filter_anisotropic")
+    {
+        return 1.0;
+    }
+
+    let mut max = 1.0;
+    unsafe {
+        gl::GetFloatv(MAX_TEXTURE_MAX_ANISOTROPY, &mut max);
+    }
+    max
+}
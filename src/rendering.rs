@@ -0,0 +1,505 @@
+use gl::types::*;
+
+use crate::debug::supports_program_pipelines;
+use crate::error::Error;
+
+/// Clears the colour buffer within the given rectangle only, restoring the previous scissor test
+/// state and clear colour afterwards.
+#[allow(clippy::too_many_arguments)]
+pub fn clear_rect(
+    x: GLint,
+    y: GLint,
+    width: GLsizei,
+    height: GLsizei,
+    r: GLfloat,
+    g: GLfloat,
+    b: GLfloat,
+    a: GLfloat,
+) {
+    let mut prev_box = [0 as GLint; 4];
+    let [prev_r, prev_g, prev_b, prev_a] = get_clear_color();
+
+    unsafe {
+        let scissor_was_enabled = gl::IsEnabled(gl::SCISSOR_TEST) == gl::TRUE;
+        gl::GetIntegerv(gl::SCISSOR_BOX, prev_box.as_mut_ptr());
+
+        gl::Enable(gl::SCISSOR_TEST);
+        gl::Scissor(x, y, width, height);
+        set_clear_color(r, g, b, a);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+
+        set_clear_color(prev_r, prev_g, prev_b, prev_a);
+        gl::Scissor(prev_box[0], prev_box[1], prev_box[2], prev_box[3]);
+        if !scissor_was_enabled {
+            gl::Disable(gl::SCISSOR_TEST);
+        }
+    }
+}
+
+// Only available in compatibility profile contexts, so not part of the core bindings.
+const POINT_SMOOTH: GLenum = 0x0B10;
+const POINT_SMOOTH_HINT: GLenum = 0x0C51;
+
+/// Enables anti-aliased line rasterisation. Blending must also be enabled for the smoothing to be
+/// visible.
+pub fn enable_line_smooth() {
+    unsafe {
+        gl::Enable(gl::LINE_SMOOTH);
+        gl::Hint(gl::LINE_SMOOTH_HINT, gl::NICEST);
+    }
+}
+
+pub fn disable_line_smooth() {
+    unsafe {
+        gl::Disable(gl::LINE_SMOOTH);
+    }
+}
+
+/// Enables anti-aliased point rasterisation. Blending must also be enabled for the smoothing to
+/// be visible. Only has an effect on compatibility profile contexts.
+pub fn enable_point_smooth() {
+    unsafe {
+        gl::Enable(POINT_SMOOTH);
+        gl::Hint(POINT_SMOOTH_HINT, gl::NICEST);
+    }
+}
+
+pub fn disable_point_smooth() {
+    unsafe {
+        gl::Disable(POINT_SMOOTH);
+    }
+}
+
+/// Clears the draw buffer at the given index (i.e. a single colour attachment of a framebuffer
+/// with multiple render targets) to the given colour.
+pub fn clear_color_buffer(index: GLint, r: GLfloat, g: GLfloat, b: GLfloat, a: GLfloat) {
+    let colour = [r, g, b, a];
+    unsafe {
+        gl::ClearBufferfv(gl::COLOR, index, colour.as_ptr());
+    }
+}
+
+/// Clears the depth buffer to the given value.
+pub fn clear_depth_buffer(value: GLfloat) {
+    unsafe {
+        gl::ClearBufferfv(gl::DEPTH, 0, &value);
+    }
+}
+
+/// Sets which colour components are written to the colour buffer.
+pub fn set_color_mask(r: bool, g: bool, b: bool, a: bool) {
+    unsafe {
+        gl::ColorMask(
+            r as GLboolean,
+            g as GLboolean,
+            b as GLboolean,
+            a as GLboolean,
+        );
+    }
+}
+
+/// Sets whether fragments write to the depth buffer. Depth testing is unaffected.
+pub fn set_depth_mask(write: bool) {
+    unsafe {
+        gl::DepthMask(write as GLboolean);
+    }
+}
+
+/// Allows the vertex shader to set the size of rasterised points by writing to `gl_PointSize`.
+pub fn enable_program_point_size() {
+    unsafe {
+        gl::Enable(gl::PROGRAM_POINT_SIZE);
+    }
+}
+
+pub fn disable_program_point_size() {
+    unsafe {
+        gl::Disable(gl::PROGRAM_POINT_SIZE);
+    }
+}
+
+/// Blocks until all previously issued GL commands have completed.
+pub fn finish() {
+    unsafe {
+        gl::Finish();
+    }
+}
+
+/// Forces previously issued GL commands to be submitted without waiting for them to complete.
+pub fn flush() {
+    unsafe {
+        gl::Flush();
+    }
+}
+
+/// Restores the GL state that this crate can modify to its defaults, which is useful after
+/// interleaving with other code that uses GL directly.
+pub fn reset_state() {
+    unsafe {
+        gl::BindVertexArray(0);
+        gl::UseProgram(0);
+        if supports_program_pipelines() {
+            gl::BindProgramPipeline(0);
+        }
+        gl::ActiveTexture(gl::TEXTURE0);
+        gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+
+        gl::Disable(gl::BLEND);
+        gl::Disable(gl::DEPTH_TEST);
+        gl::Disable(gl::CULL_FACE);
+        gl::Disable(gl::SCISSOR_TEST);
+        gl::Disable(gl::LINE_SMOOTH);
+        gl::Disable(gl::PROGRAM_POINT_SIZE);
+        gl::Disable(gl::TEXTURE_CUBE_MAP_SEAMLESS);
+        gl::Disable(gl::DEPTH_CLAMP);
+
+        let mut max_clip_distances: GLint = 0;
+        gl::GetIntegerv(gl::MAX_CLIP_DISTANCES, &mut max_clip_distances);
+        for i in 0..max_clip_distances.max(0) as GLuint {
+            gl::Disable(gl::CLIP_DISTANCE0 + i);
+        }
+
+        gl::PolygonMode(gl::FRONT_AND_BACK, gl::FILL);
+        gl::ColorMask(gl::TRUE, gl::TRUE, gl::TRUE, gl::TRUE);
+        gl::DepthMask(gl::TRUE);
+        gl::DepthRange(0.0, 1.0);
+        gl::ClearColor(0.0, 0.0, 0.0, 0.0);
+        gl::PointParameteri(gl::POINT_SPRITE_COORD_ORIGIN, gl::UPPER_LEFT as GLint);
+        gl::PointParameterf(gl::POINT_FADE_THRESHOLD_SIZE, 1.0);
+    }
+}
+
+// The texture targets unbound by `unbind_texture_unit`.
+const TEXTURE_TARGETS: [GLenum; 5] = [
+    gl::TEXTURE_1D,
+    gl::TEXTURE_2D,
+    gl::TEXTURE_3D,
+    gl::TEXTURE_2D_ARRAY,
+    gl::TEXTURE_CUBE_MAP,
+];
+
+/// Unbinds textures from the given texture unit for all of the common texture targets, leaving
+/// that unit active. Useful when a texture is about to be rendered to after being sampled.
+pub fn unbind_texture_unit(unit: GLuint) {
+    unsafe {
+        gl::ActiveTexture(gl::TEXTURE0 + unit);
+        for target in TEXTURE_TARGETS {
+            gl::BindTexture(target, 0);
+        }
+    }
+}
+
+/// Unbinds textures from the first `count` texture units for all of the common texture targets,
+/// leaving texture unit 0 active.
+pub fn unbind_all_textures(count: GLuint) {
+    for unit in 0..count {
+        unbind_texture_unit(unit);
+    }
+    unsafe {
+        gl::ActiveTexture(gl::TEXTURE0);
+    }
+}
+
+/// Enables filtering across cubemap face edges to avoid visible seams.
+pub fn enable_seamless_cubemaps() {
+    unsafe {
+        gl::Enable(gl::TEXTURE_CUBE_MAP_SEAMLESS);
+    }
+}
+
+pub fn disable_seamless_cubemaps() {
+    unsafe {
+        gl::Disable(gl::TEXTURE_CUBE_MAP_SEAMLESS);
+    }
+}
+
+/// Sets the mapping of normalised device depth values to window depth values. Defaults to
+/// `(0.0, 1.0)`.
+pub fn set_depth_range(near: GLdouble, far: GLdouble) {
+    unsafe {
+        gl::DepthRange(near, far);
+    }
+}
+
+/// Something that can be rendered to, allowing code to target either the screen or an off-screen
+/// framebuffer interchangeably.
+pub trait RenderTarget {
+    /// Binds the target so that subsequent draw calls render to it.
+    fn bind(&self);
+}
+
+/// A buffer of the default framebuffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefaultFramebufferAttachment {
+    Color,
+    Depth,
+    Stencil,
+}
+
+impl From<DefaultFramebufferAttachment> for GLenum {
+    fn from(attachment: DefaultFramebufferAttachment) -> GLenum {
+        match attachment {
+            DefaultFramebufferAttachment::Color => gl::COLOR,
+            DefaultFramebufferAttachment::Depth => gl::DEPTH,
+            DefaultFramebufferAttachment::Stencil => gl::STENCIL,
+        }
+    }
+}
+
+/// The window-system-provided framebuffer.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultFramebuffer;
+
+impl DefaultFramebuffer {
+    /// Hints that the contents of the given buffers are no longer needed, which avoids writing
+    /// them back to memory on tiled GPUs. The default framebuffer is temporarily bound as the draw
+    /// framebuffer and the previous draw framebuffer binding is restored afterwards; the read
+    /// framebuffer binding is not touched.
+    pub fn invalidate(&self, attachments: &[DefaultFramebufferAttachment]) {
+        let attachments: Vec<GLenum> = attachments.iter().map(|&a| a.into()).collect();
+        let mut prev_draw_framebuffer: GLint = 0;
+
+        unsafe {
+            gl::GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut prev_draw_framebuffer);
+
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, 0);
+            gl::InvalidateFramebuffer(
+                gl::DRAW_FRAMEBUFFER,
+                attachments.len() as GLsizei,
+                attachments.as_ptr(),
+            );
+
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, prev_draw_framebuffer as GLuint);
+        }
+    }
+}
+
+impl RenderTarget for DefaultFramebuffer {
+    fn bind(&self) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+    }
+}
+
+/// Sets the colour used when clearing the colour buffer.
+pub fn set_clear_color(r: GLfloat, g: GLfloat, b: GLfloat, a: GLfloat) {
+    unsafe {
+        gl::ClearColor(r, g, b, a);
+    }
+}
+
+/// Gets the current clear colour as `[r, g, b, a]`.
+pub fn get_clear_color() -> [GLfloat; 4] {
+    let mut colour = [0.0; 4];
+    unsafe {
+        gl::GetFloatv(gl::COLOR_CLEAR_VALUE, colour.as_mut_ptr());
+    }
+    colour
+}
+
+/// Where `gl_PointCoord` has its origin within a point sprite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PointSpriteCoordOrigin {
+    #[default]
+    UpperLeft,
+    LowerLeft,
+}
+
+impl From<PointSpriteCoordOrigin> for GLenum {
+    fn from(origin: PointSpriteCoordOrigin) -> GLenum {
+        match origin {
+            PointSpriteCoordOrigin::UpperLeft => gl::UPPER_LEFT,
+            PointSpriteCoordOrigin::LowerLeft => gl::LOWER_LEFT,
+        }
+    }
+}
+
+impl TryFrom<GLenum> for PointSpriteCoordOrigin {
+    type Error = Error;
+
+    fn try_from(value: GLenum) -> Result<Self, Error> {
+        match value {
+            gl::UPPER_LEFT => Ok(PointSpriteCoordOrigin::UpperLeft),
+            gl::LOWER_LEFT => Ok(PointSpriteCoordOrigin::LowerLeft),
+            other => Err(Error::UnknownEnum(other)),
+        }
+    }
+}
+
+/// Sets the origin of `gl_PointCoord` for point sprites. Defaults to the upper left.
+pub fn set_point_sprite_coord_origin(origin: PointSpriteCoordOrigin) {
+    unsafe {
+        gl::PointParameteri(gl::POINT_SPRITE_COORD_ORIGIN, GLenum::from(origin) as GLint);
+    }
+}
+
+/// Sets the size below which multisampled points fade out rather than shrinking further.
+/// Defaults to 1.0.
+pub fn set_point_fade_threshold(size: GLfloat) {
+    unsafe {
+        gl::PointParameterf(gl::POINT_FADE_THRESHOLD_SIZE, size);
+    }
+}
+
+/// Gets the current origin of `gl_PointCoord` for point sprites. Returns
+/// [`Error::UnknownEnum`] if GL reports an unexpected value.
+pub fn get_point_sprite_coord_origin() -> Result<PointSpriteCoordOrigin, Error> {
+    let mut origin: GLint = 0;
+    unsafe {
+        gl::GetIntegerv(gl::POINT_SPRITE_COORD_ORIGIN, &mut origin);
+    }
+
+    PointSpriteCoordOrigin::try_from(origin as GLenum)
+}
+
+/// Gets the current point fade threshold size.
+pub fn get_point_fade_threshold() -> GLfloat {
+    let mut size = 0.0;
+    unsafe {
+        gl::GetFloatv(gl::POINT_FADE_THRESHOLD_SIZE, &mut size);
+    }
+    size
+}
+
+/// Enables depth clamping, so primitives are not clipped against the near and far planes and their
+/// depth values are clamped instead.
+pub fn enable_depth_clamp() {
+    unsafe {
+        gl::Enable(gl::DEPTH_CLAMP);
+    }
+}
+
+pub fn disable_depth_clamp() {
+    unsafe {
+        gl::Disable(gl::DEPTH_CLAMP);
+    }
+}
+
+/// Enables the user clip plane with the given index, so that primitives are clipped where the
+/// vertex shader writes a negative value to `gl_ClipDistance[index]`.
+pub fn enable_clip_distance(index: GLuint) {
+    unsafe {
+        gl::Enable(gl::CLIP_DISTANCE0 + index);
+    }
+}
+
+pub fn disable_clip_distance(index: GLuint) {
+    unsafe {
+        gl::Disable(gl::CLIP_DISTANCE0 + index);
+    }
+}
+
+/// Reads a single unsigned integer pixel (e.g. an object ID for picking) from the current read
+/// buffer, which must have an unsigned integer format. `x` and `y` are window coordinates with the
+/// origin at the top left, as given by most windowing libraries - the window (or framebuffer)
+/// height is needed to flip them to OpenGL's bottom left origin.
+pub fn read_pixel_u32(x: GLint, y: GLint, window_height: GLint) -> u32 {
+    let mut value: GLuint = 0;
+
+    unsafe {
+        gl::ReadPixels(
+            x,
+            window_to_gl_pixel_y(y, window_height),
+            1,
+            1,
+            gl::RED_INTEGER,
+            gl::UNSIGNED_INT,
+            &mut value as *mut GLuint as *mut _,
+        );
+    }
+
+    value
+}
+
+fn window_to_gl_pixel_y(y: GLint, window_height: GLint) -> GLint {
+    window_height - 1 - y
+}
+
+/// A viewport rectangle in OpenGL window coordinates (i.e. with the origin at the bottom left).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Viewport {
+    pub x: GLint,
+    pub y: GLint,
+    pub width: GLsizei,
+    pub height: GLsizei,
+}
+
+pub fn set_viewport(viewport: Viewport) {
+    unsafe {
+        gl::Viewport(viewport.x, viewport.y, viewport.width, viewport.height);
+    }
+}
+
+pub fn get_viewport() -> Viewport {
+    let mut values = [0 as GLint; 4];
+    unsafe {
+        gl::GetIntegerv(gl::VIEWPORT, values.as_mut_ptr());
+    }
+
+    Viewport {
+        x: values[0],
+        y: values[1],
+        width: values[2],
+        height: values[3],
+    }
+}
+
+/// Converts window coordinates with the origin at the top left (as given by most windowing
+/// libraries) to normalised device coordinates within the given viewport. The window height is
+/// needed to flip the y axis to OpenGL's bottom left origin.
+pub fn window_to_ndc(x: f32, y: f32, viewport: &Viewport, window_height: f32) -> [f32; 2] {
+    let gl_y = window_height - y;
+    [
+        2.0 * (x - viewport.x as f32) / viewport.width as f32 - 1.0,
+        2.0 * (gl_y - viewport.y as f32) / viewport.height as f32 - 1.0,
+    ]
+}
+
+/// The inverse of [`window_to_ndc`].
+pub fn ndc_to_window(ndc: [f32; 2], viewport: &Viewport, window_height: f32) -> [f32; 2] {
+    let gl_y = viewport.y as f32 + (ndc[1] + 1.0) * 0.5 * viewport.height as f32;
+    [
+        viewport.x as f32 + (ndc[0] + 1.0) * 0.5 * viewport.width as f32,
+        window_height - gl_y,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pixel_y_flip_ignores_viewport() {
+        // Bottom half of an 800x600 window, as in split-screen.
+        let viewport = Viewport {
+            x: 0,
+            y: 0,
+            width: 800,
+            height: 300,
+        };
+
+        // The bottom row of the window is the bottom row of the viewport...
+        assert_eq!(window_to_gl_pixel_y(599, 600), viewport.y);
+        // ...and a point three quarters of the way down the window is halfway up the viewport.
+        assert_eq!(window_to_gl_pixel_y(450, 600), viewport.y + 149);
+        assert_eq!(window_to_gl_pixel_y(0, 600), 599);
+    }
+
+    #[test]
+    fn window_ndc_conversion() {
+        // Right half of an 800x600 window.
+        let viewport = Viewport {
+            x: 400,
+            y: 0,
+            width: 400,
+            height: 600,
+        };
+
+        assert_eq!(window_to_ndc(400.0, 0.0, &viewport, 600.0), [-1.0, 1.0]);
+        assert_eq!(window_to_ndc(800.0, 600.0, &viewport, 600.0), [1.0, -1.0]);
+        assert_eq!(window_to_ndc(600.0, 300.0, &viewport, 600.0), [0.0, 0.0]);
+        assert_eq!(ndc_to_window([0.5, -0.5], &viewport, 600.0), [700.0, 450.0]);
+        assert_eq!(window_to_ndc(700.0, 450.0, &viewport, 600.0), [0.5, -0.5]);
+    }
+}
@@ -1,10 +1,39 @@
 use gl::types::*;
 
 use std::ffi::CString;
+use std::io::Read;
 use std::path::Path;
+use std::rc::Rc;
 
 use crate::error::{Error, ShaderError};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShaderType {
+    Vertex,
+    Fragment,
+}
+
+impl From<ShaderType> for GLenum {
+    fn from(ty: ShaderType) -> GLenum {
+        match ty {
+            ShaderType::Vertex => gl::VERTEX_SHADER,
+            ShaderType::Fragment => gl::FRAGMENT_SHADER,
+        }
+    }
+}
+
+impl TryFrom<GLenum> for ShaderType {
+    type Error = Error;
+
+    fn try_from(value: GLenum) -> Result<Self, Error> {
+        match value {
+            gl::VERTEX_SHADER => Ok(ShaderType::Vertex),
+            gl::FRAGMENT_SHADER => Ok(ShaderType::Fragment),
+            other => Err(Error::UnknownEnum(other)),
+        }
+    }
+}
+
 #[allow(drop_bounds)]
 pub trait Shader: Drop {
     fn from_source(src: &str) -> Result<Self, Error>
@@ -19,6 +48,36 @@ pub trait Shader: Drop {
         Self::from_source(&src)
     }
 
+    /// Reads the entire shader source from the given reader, e.g. for sources embedded in the
+    /// binary or loaded from a virtual file system.
+    fn from_reader(mut reader: impl Read) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        let mut src = String::new();
+        reader
+            .read_to_string(&mut src)
+            .map_err(|e| Error::Shader(ShaderError::from(e)))?;
+        Self::from_source(&src)
+    }
+
+    /// Like [`Shader::from_source`] but prepends a `#version {version}` directive unless the
+    /// source already begins with one.
+    fn from_source_with_version(src: &str, version: &str) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        if src.trim().is_empty() {
+            return Err(Error::Shader(ShaderError::EmptySource));
+        }
+
+        if src.trim_start().starts_with("#version") {
+            Self::from_source(src)
+        } else {
+            Self::from_source(&format!("#version {}\n{}", version, src))
+        }
+    }
+
     fn get_id(&self) -> GLuint;
 }
 
@@ -28,7 +87,7 @@ pub struct Vertex {
 
 impl Shader for Vertex {
     fn from_source(src: &str) -> Result<Self, Error> {
-        make_shader(src, gl::VERTEX_SHADER).map(|id| Self { id })
+        make_shader(src, ShaderType::Vertex).map(|id| Self { id })
     }
 
     fn get_id(&self) -> GLuint {
@@ -50,7 +109,7 @@ pub struct Fragment {
 
 impl Shader for Fragment {
     fn from_source(src: &str) -> Result<Self, Error> {
-        make_shader(src, gl::FRAGMENT_SHADER).map(|id| Self { id })
+        make_shader(src, ShaderType::Fragment).map(|id| Self { id })
     }
 
     fn get_id(&self) -> GLuint {
@@ -66,34 +125,212 @@ impl Drop for Fragment {
     }
 }
 
+/// A linked shader program. Cloning is cheap as clones share the same underlying GL program, which
+/// is deleted once the last clone is dropped.
+#[derive(Clone)]
 pub struct Program {
+    inner: Rc<ProgramInner>,
+}
+
+struct ProgramInner {
     id: GLuint,
 }
 
 impl Program {
+    /// Links the given shaders into a program. The shaders are consumed as they are no longer
+    /// needed once linking is complete - they are detached and deleted before this returns.
     pub fn new(vert: Vertex, frag: Fragment) -> Result<Self, Error> {
         let id = unsafe { gl::CreateProgram() };
+        link_program(id, &[vert.get_id(), frag.get_id()])
+    }
+
+    /// Compiles and links a program from a single file containing both shader stages, each
+    /// preceded by a marker line of the form `//--- vertex` or `//--- fragment`. Any text before
+    /// the first marker (e.g. a licence header or a common `#version` directive) is shared by both
+    /// stages.
+    pub fn from_combined_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let src = std::fs::read_to_string(path).map_err(|e| Error::Shader(ShaderError::from(e)))?;
+        Self::from_combined_source(&src)
+    }
+
+    /// Links a single shader into a separable program for use as one stage of a
+    /// [`ProgramPipeline`].
+    pub fn separable(shader: impl Shader) -> Result<Self, Error> {
+        let id = unsafe { gl::CreateProgram() };
+        unsafe {
+            gl::ProgramParameteri(id, gl::PROGRAM_SEPARABLE, gl::TRUE as GLint);
+        }
+        link_program(id, &[shader.get_id()])
+    }
+
+    /// See [`Program::from_combined_file`].
+    pub fn from_combined_source(src: &str) -> Result<Self, Error> {
+        let (vert_src, frag_src) = split_combined_source(src)?;
+        Self::from_sources(&vert_src, &frag_src)
+    }
+
+    /// Compiles vertex and fragment shaders from the given sources and links them.
+    pub fn from_sources(vert_src: &str, frag_src: &str) -> Result<Self, Error> {
+        Program::new(
+            Vertex::from_source(vert_src)?,
+            Fragment::from_source(frag_src)?,
+        )
+    }
 
-        let mut success = gl::TRUE as GLint;
+    /// Returns the program's info log regardless of whether linking succeeded, which can contain
+    /// driver warnings even for a successfully linked program.
+    pub fn info_log(&self) -> String {
+        get_program_error_msg(self.get_id())
+    }
+
+    /// Lists the program's active uniform blocks.
+    pub fn uniform_blocks(&self) -> Vec<BlockInfo> {
+        self.blocks(gl::UNIFORM_BLOCK)
+    }
+
+    /// Lists the program's active shader storage blocks.
+    pub fn storage_blocks(&self) -> Vec<BlockInfo> {
+        self.blocks(gl::SHADER_STORAGE_BLOCK)
+    }
+
+    fn blocks(&self, interface: GLenum) -> Vec<BlockInfo> {
+        let id = self.get_id();
+        let mut count: GLint = 0;
+        let mut max_name_len: GLint = 0;
+
+        unsafe {
+            gl::GetProgramInterfaceiv(id, interface, gl::ACTIVE_RESOURCES, &mut count);
+            gl::GetProgramInterfaceiv(id, interface, gl::MAX_NAME_LENGTH, &mut max_name_len);
+        }
+
+        (0..count.max(0) as GLuint)
+            .map(|index| {
+                let props = [gl::BUFFER_BINDING, gl::BUFFER_DATA_SIZE];
+                let mut values = [0 as GLint; 2];
+                let mut name = vec![0u8; max_name_len.max(0) as usize];
+                let mut name_len: GLsizei = 0;
+
+                unsafe {
+                    gl::GetProgramResourceiv(
+                        id,
+                        interface,
+                        index,
+                        props.len() as GLsizei,
+                        props.as_ptr(),
+                        values.len() as GLsizei,
+                        std::ptr::null_mut(),
+                        values.as_mut_ptr(),
+                    );
+                    gl::GetProgramResourceName(
+                        id,
+                        interface,
+                        index,
+                        max_name_len,
+                        &mut name_len,
+                        name.as_mut_ptr() as *mut GLchar,
+                    );
+                }
+
+                name.truncate(name_len.max(0) as usize);
+
+                BlockInfo {
+                    name: String::from_utf8_lossy(&name).into_owned(),
+                    index,
+                    binding: values[0] as GLuint,
+                    data_size: values[1] as usize,
+                }
+            })
+            .collect()
+    }
+
+    /// Looks up the index of the named subroutine function in the given stage.
+    pub fn subroutine_index(&self, stage: ShaderType, name: &str) -> Result<GLuint, Error> {
+        let name_c_str = CString::new(name)?;
+        let index =
+            unsafe { gl::GetSubroutineIndex(self.get_id(), stage.into(), name_c_str.as_ptr()) };
+
+        if index == gl::INVALID_INDEX {
+            return Err(Error::Shader(ShaderError::SubroutineNotFound(
+                name.to_string(),
+            )));
+        }
+
+        Ok(index)
+    }
+
+    /// Looks up the location of the named subroutine uniform in the given stage. The location is
+    /// the position in the slice passed to [`Program::set_subroutines`].
+    pub fn subroutine_uniform_location(
+        &self,
+        stage: ShaderType,
+        name: &str,
+    ) -> Result<GLuint, Error> {
+        let name_c_str = CString::new(name)?;
+        let location = unsafe {
+            gl::GetSubroutineUniformLocation(self.get_id(), stage.into(), name_c_str.as_ptr())
+        };
 
+        if location < 0 {
+            return Err(Error::Shader(ShaderError::SubroutineNotFound(
+                name.to_string(),
+            )));
+        }
+
+        Ok(location as GLuint)
+    }
+
+    /// Sets the subroutine index for every active subroutine uniform location of the given
+    /// stage, where `indices[location]` is the subroutine index for that location. The program
+    /// must currently be in use, and the selection is reset whenever a program is used again.
+    ///
+    /// Returns an error if this program is not the one in use or if `indices` does not have
+    /// exactly one entry per active subroutine uniform location of the stage.
+    pub fn set_subroutines(&self, stage: ShaderType, indices: &[GLuint]) -> Result<(), Error> {
+        let mut current: GLint = 0;
+        let mut locations: GLint = 0;
         unsafe {
-            gl::AttachShader(id, vert.get_id());
-            gl::AttachShader(id, frag.get_id());
-            gl::LinkProgram(id);
+            gl::GetIntegerv(gl::CURRENT_PROGRAM, &mut current);
+            gl::GetProgramStageiv(
+                self.get_id(),
+                stage.into(),
+                gl::ACTIVE_SUBROUTINE_UNIFORM_LOCATIONS,
+                &mut locations,
+            );
+        }
 
-            let success_ptr: *mut i32 = &mut success;
-            gl::GetProgramiv(id, gl::LINK_STATUS, success_ptr);
+        if current as GLuint != self.get_id() {
+            return Err(Error::Shader(ShaderError::ProgramNotInUse));
+        }
+        if indices.len() != locations as usize {
+            return Err(Error::Shader(ShaderError::SubroutineCountMismatch {
+                expected: locations as usize,
+                found: indices.len(),
+            }));
         }
 
-        if success as GLboolean == gl::FALSE {
-            return Err(Error::Shader(ShaderError::Linking("TODO".to_string())));
+        unsafe {
+            gl::UniformSubroutinesuiv(stage.into(), indices.len() as GLsizei, indices.as_ptr());
         }
 
-        Ok(Program { id })
+        Ok(())
     }
+
+    pub fn get_id(&self) -> GLuint {
+        self.inner.id
+    }
+}
+
+/// Describes an active uniform or shader storage block of a [`Program`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockInfo {
+    pub name: String,
+    pub index: GLuint,
+    pub binding: GLuint,
+    /// Minimum size in bytes of a buffer bound to this block.
+    pub data_size: usize,
 }
 
-impl Drop for Program {
+impl Drop for ProgramInner {
     fn drop(&mut self) {
         unsafe {
             gl::DeleteProgram(self.id);
@@ -101,8 +338,121 @@ impl Drop for Program {
     }
 }
 
-fn make_shader(src: &str, variety: GLenum) -> Result<GLuint, Error> {
-    let id = unsafe { gl::CreateShader(variety) };
+/// Combines separable programs (see [`Program::separable`]) so that stages can be swapped without
+/// relinking. The pipeline does not take ownership of the programs, so they must outlive its use.
+pub struct ProgramPipeline {
+    id: GLuint,
+}
+
+impl ProgramPipeline {
+    pub fn new() -> Self {
+        let mut id = 0;
+        unsafe {
+            gl::GenProgramPipelines(1, &mut id);
+        }
+        ProgramPipeline { id }
+    }
+
+    pub fn set_vertex_program(&self, prog: &Program) {
+        unsafe {
+            gl::UseProgramStages(self.id, gl::VERTEX_SHADER_BIT, prog.get_id());
+        }
+    }
+
+    pub fn set_fragment_program(&self, prog: &Program) {
+        unsafe {
+            gl::UseProgramStages(self.id, gl::FRAGMENT_SHADER_BIT, prog.get_id());
+        }
+    }
+
+    /// Binds the pipeline. Note that a program made current with `glUseProgram` takes precedence
+    /// over a bound pipeline.
+    pub fn bind(&self) {
+        unsafe {
+            gl::BindProgramPipeline(self.id);
+        }
+    }
+}
+
+impl Default for ProgramPipeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for ProgramPipeline {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteProgramPipelines(1, &self.id);
+        }
+    }
+}
+
+fn link_program(id: GLuint, shader_ids: &[GLuint]) -> Result<Program, Error> {
+    let mut success = gl::TRUE as GLint;
+
+    unsafe {
+        for &shader_id in shader_ids {
+            gl::AttachShader(id, shader_id);
+        }
+        gl::LinkProgram(id);
+
+        let success_ptr: *mut i32 = &mut success;
+        gl::GetProgramiv(id, gl::LINK_STATUS, success_ptr);
+
+        for &shader_id in shader_ids {
+            gl::DetachShader(id, shader_id);
+        }
+    }
+
+    let prog = Program {
+        inner: Rc::new(ProgramInner { id }),
+    };
+
+    if success as GLboolean == gl::FALSE {
+        return Err(Error::Shader(ShaderError::Linking(get_program_error_msg(
+            id,
+        ))));
+    }
+
+    Ok(prog)
+}
+
+// A space is required after the marker so that decorative comments like `//------` are not
+// mistaken for section markers.
+const SECTION_MARKER: &str = "//--- ";
+
+fn split_combined_source(src: &str) -> Result<(String, String), ShaderError> {
+    let mut preamble = String::new();
+    let mut vert: Option<String> = None;
+    let mut frag: Option<String> = None;
+    let mut current: &mut String = &mut preamble;
+
+    for line in src.lines() {
+        if let Some(name) = line.trim().strip_prefix(SECTION_MARKER) {
+            current = match name.trim() {
+                "vertex" => vert.insert(String::new()),
+                "fragment" => frag.insert(String::new()),
+                other => return Err(ShaderError::UnknownSection(other.to_string())),
+            };
+        } else {
+            current.push_str(line);
+            current.push('\n');
+        }
+    }
+
+    let vert = vert.ok_or(ShaderError::MissingSection("vertex"))?;
+    let frag = frag.ok_or(ShaderError::MissingSection("fragment"))?;
+
+    Ok((preamble.clone() + &vert, preamble + &frag))
+}
+
+fn make_shader(src: &str, variety: ShaderType) -> Result<GLuint, Error> {
+    if src.trim().is_empty() {
+        return Err(Error::Shader(ShaderError::EmptySource));
+    }
+
+    let id = unsafe { gl::CreateShader(variety.into()) };
 
     let src_c_str = CString::new(src)?;
     let mut success = gl::TRUE as GLint;
@@ -119,8 +469,99 @@ fn make_shader(src: &str, variety: GLenum) -> Result<GLuint, Error> {
     }
 
     if success as GLboolean == gl::FALSE {
-        return Err(Error::Shader(ShaderError::Compilation("TODO".to_string())));
+        let msg = get_shader_error_msg(id);
+        unsafe {
+            gl::DeleteShader(id);
+        }
+        return Err(Error::Shader(ShaderError::Compilation(msg)));
     }
 
     Ok(id)
 }
+
+fn get_shader_error_msg(id: GLuint) -> String {
+    let mut len: GLint = 0;
+    unsafe {
+        gl::GetShaderiv(id, gl::INFO_LOG_LENGTH, &mut len);
+    }
+
+    let mut buf = vec![0u8; len.max(0) as usize];
+    let mut written: GLsizei = 0;
+    unsafe {
+        gl::GetShaderInfoLog(id, len, &mut written, buf.as_mut_ptr() as *mut GLchar);
+    }
+
+    info_log_to_string(buf, written)
+}
+
+fn get_program_error_msg(id: GLuint) -> String {
+    let mut len: GLint = 0;
+    unsafe {
+        gl::GetProgramiv(id, gl::INFO_LOG_LENGTH, &mut len);
+    }
+
+    let mut buf = vec![0u8; len.max(0) as usize];
+    let mut written: GLsizei = 0;
+    unsafe {
+        gl::GetProgramInfoLog(id, len, &mut written, buf.as_mut_ptr() as *mut GLchar);
+    }
+
+    info_log_to_string(buf, written)
+}
+
+fn info_log_to_string(mut buf: Vec<u8>, written: GLsizei) -> String {
+    buf.truncate(written.max(0) as usize);
+    String::from_utf8_lossy(&buf).trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shader_type_glenum_round_trip() {
+        for ty in [ShaderType::Vertex, ShaderType::Fragment] {
+            assert_eq!(ShaderType::try_from(GLenum::from(ty)).unwrap(), ty);
+        }
+        assert!(matches!(
+            ShaderType::try_from(gl::COMPUTE_SHADER),
+            Err(Error::UnknownEnum(gl::COMPUTE_SHADER))
+        ));
+    }
+
+    #[test]
+    fn split_combined_source_sections() {
+        let src = "//--- vertex\nvoid main() {}\n//--- fragment\nout vec4 c;\n";
+        let (vert, frag) = split_combined_source(src).unwrap();
+        assert_eq!(vert, "void main() {}\n");
+        assert_eq!(frag, "out vec4 c;\n");
+    }
+
+    #[test]
+    fn split_combined_source_errors() {
+        assert!(matches!(
+            split_combined_source("//--- geometry\n"),
+            Err(ShaderError::UnknownSection(name)) if name == "geometry"
+        ));
+        assert!(matches!(
+            split_combined_source("//--- vertex\nvoid main() {}\n"),
+            Err(ShaderError::MissingSection("fragment"))
+        ));
+    }
+
+    #[test]
+    fn split_combined_source_preamble() {
+        let src = "// shared\n#version 330 core\n//--- vertex\nvoid main() {}\n//--- fragment\nout vec4 c;\n";
+        let (vert, frag) = split_combined_source(src).unwrap();
+        assert_eq!(vert, "// shared\n#version 330 core\nvoid main() {}\n");
+        assert_eq!(frag, "// shared\n#version 330 core\nout vec4 c;\n");
+    }
+
+    #[test]
+    fn split_combined_source_ignores_decorative_comments() {
+        let src = "//----------\n//--- vertex\n//---\nvoid main() {}\n//--- fragment\n//------\n";
+        let (vert, frag) = split_combined_source(src).unwrap();
+        assert_eq!(vert, "//----------\n//---\nvoid main() {}\n");
+        assert_eq!(frag, "//----------\n//------\n");
+    }
+}
@@ -0,0 +1,293 @@
+use std::f32::consts::PI;
+
+/// Vertex data for a mesh, with one normal and texture coordinate per position and indices
+/// describing counter-clockwise wound triangles.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MeshData {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub uvs: Vec<[f32; 2]>,
+    pub indices: Vec<u32>,
+}
+
+impl MeshData {
+    /// Computes the axis-aligned bounding box of the positions as `(min, max)`, or `None` if the
+    /// mesh has no vertices.
+    pub fn aabb(&self) -> Option<([f32; 3], [f32; 3])> {
+        let first = *self.positions.first()?;
+
+        Some(
+            self.positions
+                .iter()
+                .fold((first, first), |(mut min, mut max), pos| {
+                    for k in 0..3 {
+                        min[k] = min[k].min(pos[k]);
+                        max[k] = max[k].max(pos[k]);
+                    }
+                    (min, max)
+                }),
+        )
+    }
+}
+
+/// A 1x1 plane in the XZ plane centred on the origin and facing +Y, divided into the given number
+/// of segments along each axis.
+pub fn plane(width_segments: u32, height_segments: u32) -> MeshData {
+    let w = width_segments.max(1);
+    let h = height_segments.max(1);
+
+    let mut mesh = MeshData::default();
+
+    for j in 0..=h {
+        for i in 0..=w {
+            let u = i as f32 / w as f32;
+            let v = j as f32 / h as f32;
+            mesh.positions.push([u - 0.5, 0.0, v - 0.5]);
+            mesh.normals.push([0.0, 1.0, 0.0]);
+            mesh.uvs.push([u, v]);
+        }
+    }
+
+    for j in 0..h {
+        for i in 0..w {
+            let a = j * (w + 1) + i;
+            let b = a + w + 1;
+            mesh.indices
+                .extend_from_slice(&[a, b, b + 1, a, b + 1, a + 1]);
+        }
+    }
+
+    mesh
+}
+
+/// A 1x1x1 cube centred on the origin. Each face has its own vertices so that normals and texture
+/// coordinates are not shared between faces.
+pub fn cube() -> MeshData {
+    // (normal, u axis, v axis) for each face where u x v = normal
+    const FACES: [([f32; 3], [f32; 3], [f32; 3]); 6] = [
+        ([1.0, 0.0, 0.0], [0.0, 0.0, -1.0], [0.0, 1.0, 0.0]),
+        ([-1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]),
+        ([0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, -1.0]),
+        ([0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+        ([0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+        ([0.0, 0.0, -1.0], [-1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+    ];
+    const CORNERS: [[f32; 2]; 4] = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+
+    let mut mesh = MeshData::default();
+
+    for (n, u, v) in FACES {
+        let base = mesh.positions.len() as u32;
+
+        for [cu, cv] in CORNERS {
+            let pos = [0, 1, 2].map(|k| 0.5 * n[k] + (cu - 0.5) * u[k] + (cv - 0.5) * v[k]);
+            mesh.positions.push(pos);
+            mesh.normals.push(n);
+            mesh.uvs.push([cu, cv]);
+        }
+
+        mesh.indices
+            .extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+
+    mesh
+}
+
+/// A UV sphere of diameter 1 centred on the origin, with `subdivisions` stacks between the poles
+/// and twice as many sectors around the Y axis.
+pub fn sphere(subdivisions: u32) -> MeshData {
+    let stacks = subdivisions.max(2);
+    let sectors = stacks * 2;
+
+    let mut mesh = MeshData::default();
+
+    for i in 0..=stacks {
+        let phi = PI * i as f32 / stacks as f32;
+
+        for j in 0..=sectors {
+            let theta = 2.0 * PI * j as f32 / sectors as f32;
+            let n = [phi.sin() * theta.cos(), phi.cos(), -phi.sin() * theta.sin()];
+
+            mesh.positions.push(n.map(|c| 0.5 * c));
+            mesh.normals.push(n);
+            mesh.uvs
+                .push([j as f32 / sectors as f32, 1.0 - i as f32 / stacks as f32]);
+        }
+    }
+
+    for i in 0..stacks {
+        for j in 0..sectors {
+            let a = i * (sectors + 1) + j;
+            let b = a + sectors + 1;
+
+            if i != 0 {
+                mesh.indices.extend_from_slice(&[a, b, a + 1]);
+            }
+            if i != stacks - 1 {
+                mesh.indices.extend_from_slice(&[a + 1, b, b + 1]);
+            }
+        }
+    }
+
+    mesh
+}
+
+/// Computes per-vertex tangents and bitangents (in that order) for normal mapping by accumulating
+/// the tangent space of every triangle that uses each vertex and then normalising. Triangles with
+/// degenerate texture coordinates are skipped.
+pub fn compute_tangents(
+    positions: &[[f32; 3]],
+    uvs: &[[f32; 2]],
+    indices: &[u32],
+) -> (Vec<[f32; 3]>, Vec<[f32; 3]>) {
+    let mut tangents = vec![[0.0; 3]; positions.len()];
+    let mut bitangents = vec![[0.0; 3]; positions.len()];
+
+    for tri in indices.chunks_exact(3) {
+        let [i0, i1, i2] = [0, 1, 2].map(|k| tri[k] as usize);
+
+        let e1 = [0, 1, 2].map(|k| positions[i1][k] - positions[i0][k]);
+        let e2 = [0, 1, 2].map(|k| positions[i2][k] - positions[i0][k]);
+        let [du1, dv1] = [0, 1].map(|k| uvs[i1][k] - uvs[i0][k]);
+        let [du2, dv2] = [0, 1].map(|k| uvs[i2][k] - uvs[i0][k]);
+
+        let det = du1 * dv2 - du2 * dv1;
+        if det.abs() <= f32::EPSILON {
+            continue;
+        }
+        let r = 1.0 / det;
+
+        let t = [0, 1, 2].map(|k| (e1[k] * dv2 - e2[k] * dv1) * r);
+        let b = [0, 1, 2].map(|k| (e2[k] * du1 - e1[k] * du2) * r);
+
+        for i in [i0, i1, i2] {
+            for k in 0..3 {
+                tangents[i][k] += t[k];
+                bitangents[i][k] += b[k];
+            }
+        }
+    }
+
+    (
+        tangents.into_iter().map(normalise).collect(),
+        bitangents.into_iter().map(normalise).collect(),
+    )
+}
+
+/// Joins index strips (e.g. for `GL_TRIANGLE_STRIP`) into a single index list with the `restart`
+/// index between each strip, for drawing in one call with primitive restart enabled.
+pub fn join_strips(strips: &[&[u32]], restart: u32) -> Vec<u32> {
+    let len =
+        strips.iter().map(|strip| strip.len()).sum::<usize>() + strips.len().saturating_sub(1);
+    let mut indices = Vec::with_capacity(len);
+
+    for (i, strip) in strips.iter().enumerate() {
+        if i != 0 {
+            indices.push(restart);
+        }
+        indices.extend_from_slice(strip);
+    }
+
+    indices
+}
+
+fn normalise(v: [f32; 3]) -> [f32; 3] {
+    let len = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    if len > 0.0 {
+        v.map(|c| c / len)
+    } else {
+        v
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+        [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+    }
+
+    fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+        [
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ]
+    }
+
+    fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+        a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+    }
+
+    // Every triangle's winding should agree with the normals of its vertices.
+    fn assert_winding_matches_normals(mesh: &MeshData) {
+        assert_eq!(mesh.positions.len(), mesh.normals.len());
+        assert_eq!(mesh.positions.len(), mesh.uvs.len());
+
+        for tri in mesh.indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|k| tri[k] as usize);
+            let face = cross(
+                sub(mesh.positions[b], mesh.positions[a]),
+                sub(mesh.positions[c], mesh.positions[a]),
+            );
+            assert!(
+                dot(face, mesh.normals[a]) > 0.0,
+                "triangle {:?} is wound clockwise",
+                tri
+            );
+        }
+    }
+
+    #[test]
+    fn plane_counts_and_winding() {
+        let mesh = plane(3, 2);
+        assert_eq!(mesh.positions.len(), 4 * 3);
+        assert_eq!(mesh.indices.len(), 3 * 2 * 6);
+        assert_winding_matches_normals(&mesh);
+    }
+
+    #[test]
+    fn cube_counts_and_winding() {
+        let mesh = cube();
+        assert_eq!(mesh.positions.len(), 24);
+        assert_eq!(mesh.indices.len(), 36);
+        assert_winding_matches_normals(&mesh);
+    }
+
+    #[test]
+    fn aabb() {
+        assert_eq!(cube().aabb(), Some(([-0.5; 3], [0.5; 3])));
+        assert_eq!(
+            plane(2, 2).aabb(),
+            Some(([-0.5, 0.0, -0.5], [0.5, 0.0, 0.5]))
+        );
+        assert_eq!(MeshData::default().aabb(), None);
+    }
+
+    #[test]
+    fn plane_tangents() {
+        let mesh = plane(2, 2);
+        let (tangents, bitangents) = compute_tangents(&mesh.positions, &mesh.uvs, &mesh.indices);
+
+        for (t, b) in tangents.iter().zip(&bitangents) {
+            assert!((t[0] - 1.0).abs() < 1e-5 && t[1].abs() < 1e-5 && t[2].abs() < 1e-5);
+            assert!(b[0].abs() < 1e-5 && b[1].abs() < 1e-5 && (b[2] - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn join_strips_inserts_restart_between_strips() {
+        assert_eq!(
+            join_strips(&[&[0, 1, 2], &[3, 4], &[5]], u32::MAX),
+            vec![0, 1, 2, u32::MAX, 3, 4, u32::MAX, 5]
+        );
+        assert_eq!(join_strips(&[&[0, 1]], u32::MAX), vec![0, 1]);
+        assert!(join_strips(&[], u32::MAX).is_empty());
+    }
+
+    #[test]
+    fn sphere_winding() {
+        assert_winding_matches_normals(&sphere(8));
+    }
+}
@@ -0,0 +1,154 @@
+use glutin::dpi::PhysicalSize;
+use glutin::event_loop::EventLoop;
+use glutin::{Api, Context, ContextBuilder, GlProfile, GlRequest, PossiblyCurrent};
+
+use std::cell::{Cell, OnceCell};
+use std::panic;
+use std::sync::{Mutex, Once};
+
+struct TestContext {
+    context: Context<PossiblyCurrent>,
+    // Kept alive for as long as the context as it may own the display connection.
+    _event_loop: Option<EventLoop<()>>,
+}
+
+thread_local! {
+    // A failure is cached too so that every test on a thread without GL does not retry creation.
+    static CONTEXT: OnceCell<Result<TestContext, String>> = const { OnceCell::new() };
+    // Set while creating the winit event loop so that its expected panic is not printed.
+    static SILENCE_PANICS: Cell<bool> = const { Cell::new(false) };
+}
+
+// The gl crate stores its function pointers in global statics, so only one thread may load them
+// and make GL calls at a time.
+static GL_LOCK: Mutex<()> = Mutex::new(());
+static PANIC_HOOK: Once = Once::new();
+
+/// Runs the given closure with an off-screen OpenGL 3.3 core context current on this thread, so
+/// that GL-touching code can be tested without a window. The context is created on first use and
+/// reused by later calls on the same thread.
+///
+/// Calls are serialised across threads as the GL function pointers are global, so tests using
+/// this can safely run in parallel with each other.
+///
+/// On Linux an OSMesa context is tried first as it requires no display; otherwise (or if OSMesa
+/// is unavailable) a headless context is created through the windowing system.
+///
+/// Returns a description of the problem if no context can be created (e.g. on a CI runner with
+/// neither a display nor OSMesa), allowing tests to be skipped.
+pub fn try_with_test_context<R>(f: impl FnOnce() -> R) -> Result<R, String> {
+    // A panicking test poisons the lock but leaves no state behind that later tests rely on.
+    let _guard = GL_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    CONTEXT.with(|ctx| match ctx.get_or_init(create_context) {
+        Ok(ctx) => {
+            gl::load_with(|name| ctx.context.get_proc_address(name) as *const _);
+            Ok(())
+        }
+        Err(e) => Err(e.clone()),
+    })?;
+    Ok(f())
+}
+
+/// Like [`try_with_test_context`] but panics if no context can be created.
+pub fn with_test_context<R>(f: impl FnOnce() -> R) -> R {
+    try_with_test_context(f).unwrap_or_else(|e| panic!("{}", e))
+}
+
+fn context_builder() -> ContextBuilder<'static, glutin::NotCurrent> {
+    ContextBuilder::new()
+        .with_gl(GlRequest::Specific(Api::OpenGl, crate::required_version()))
+        .with_gl_profile(GlProfile::Core)
+}
+
+fn create_context() -> Result<TestContext, String> {
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+    ))]
+    {
+        use glutin::platform::unix::HeadlessContextExt;
+
+        if let Ok(context) = context_builder().build_osmesa(PhysicalSize::new(1, 1)) {
+            let context = unsafe { context.make_current() }
+                .map_err(|(_, e)| format!("failed to make test context current - {}", e))?;
+            return Ok(TestContext {
+                context,
+                _event_loop: None,
+            });
+        }
+    }
+
+    // winit panics rather than returning an error when there is no display to connect to.
+    install_panic_hook();
+    SILENCE_PANICS.with(|s| s.set(true));
+    let event_loop = panic::catch_unwind(new_event_loop);
+    SILENCE_PANICS.with(|s| s.set(false));
+    let event_loop = event_loop.map_err(|panic| {
+        let reason = panic
+            .downcast_ref::<String>()
+            .map(String::as_str)
+            .or_else(|| panic.downcast_ref::<&str>().copied())
+            .unwrap_or("unknown error");
+        format!(
+            "failed to create event loop for headless test context - {}",
+            reason
+        )
+    })?;
+    let context = context_builder()
+        .build_headless(&event_loop, PhysicalSize::new(1, 1))
+        .map_err(|e| format!("failed to create headless test context - {}", e))?;
+    let context = unsafe { context.make_current() }
+        .map_err(|(_, e)| format!("failed to make test context current - {}", e))?;
+
+    Ok(TestContext {
+        context,
+        _event_loop: Some(event_loop),
+    })
+}
+
+// Wraps the existing panic hook so that it is skipped only on a thread that has set
+// `SILENCE_PANICS`, leaving panics from other tests running in parallel untouched.
+fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let prev = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !SILENCE_PANICS.with(Cell::get) {
+                prev(info);
+            }
+        }));
+    });
+}
+
+// Tests run on worker threads so the event loop must be allowed off the main thread where the
+// platform supports it.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+fn new_event_loop() -> EventLoop<()> {
+    glutin::platform::unix::EventLoopExtUnix::new_any_thread()
+}
+
+#[cfg(target_os = "windows")]
+fn new_event_loop() -> EventLoop<()> {
+    glutin::platform::windows::EventLoopExtWindows::new_any_thread()
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "windows",
+)))]
+fn new_event_loop() -> EventLoop<()> {
+    EventLoop::new()
+}